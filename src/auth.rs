@@ -0,0 +1,57 @@
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::SaltString;
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use rand::RngCore;
+use zeroize::Zeroize;
+
+// Время жизни сессии, выданной при логине
+pub const SESSION_TTL_SECONDS: i64 = 60 * 60 * 24;
+
+#[derive(Debug)]
+pub enum AuthError {
+    Hashing(String),
+}
+
+impl std::fmt::Display for AuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuthError::Hashing(msg) => write!(f, "auth error: {}", msg),
+        }
+    }
+}
+
+// Обертка над паролем в открытом виде: гарантированно обнуляет буфер при
+// выходе из области видимости, чтобы пароль не оседал в памяти.
+pub struct PlainPassword(String);
+
+impl PlainPassword {
+    pub fn new(password: String) -> PlainPassword {
+        PlainPassword(password)
+    }
+}
+
+impl Drop for PlainPassword {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+pub fn hash_password(password: &PlainPassword) -> Result<String, AuthError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.0.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AuthError::Hashing(e.to_string()))
+}
+
+pub fn verify_password(password: &PlainPassword, hash: &str) -> Result<bool, AuthError> {
+    let parsed_hash = PasswordHash::new(hash).map_err(|e| AuthError::Hashing(e.to_string()))?;
+    Ok(Argon2::default().verify_password(password.0.as_bytes(), &parsed_hash).is_ok())
+}
+
+// Генерирует криптостойкий opaque-токен сессии в виде hex-строки
+pub fn generate_session_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,62 @@
+use crate::User;
+use postgres::{Client, NoTls};
+use std::fs;
+use std::path::Path;
+
+// import <file.json>: пакетно вставляет пользователей из JSON-массива в одной
+// транзакции, пропуская и отдельно отчитываясь о дублях по email.
+pub fn import_users(db_url: &str, path: &Path) -> Result<(), String> {
+    let data = fs::read_to_string(path).map_err(|e| format!("unable to read {}: {}", path.display(), e))?;
+    let users: Vec<User> = serde_json::from_str(&data).map_err(|e| format!("invalid JSON in {}: {}", path.display(), e))?;
+
+    let mut client = Client::connect(db_url, NoTls).map_err(|e| format!("DB connection error: {}", e))?;
+    let mut tx = client.transaction().map_err(|e| format!("unable to start transaction: {}", e))?;
+
+    let mut imported = 0;
+    let mut duplicates = Vec::new();
+
+    for user in &users {
+        let exists = tx
+            .query_opt("SELECT 1 FROM users WHERE email = $1", &[&user.email])
+            .map_err(|e| format!("DB query error: {}", e))?
+            .is_some();
+
+        if exists {
+            duplicates.push(user.email.clone());
+            continue;
+        }
+
+        tx.execute("INSERT INTO users (name, email) VALUES ($1, $2)", &[&user.name, &user.email])
+            .map_err(|e| format!("DB insert error: {}", e))?;
+        imported += 1;
+    }
+
+    tx.commit().map_err(|e| format!("unable to commit transaction: {}", e))?;
+
+    println!("Imported {} user(s)", imported);
+    if !duplicates.is_empty() {
+        println!("Skipped {} duplicate(s): {}", duplicates.len(), duplicates.join(", "));
+    }
+
+    Ok(())
+}
+
+// export <file.json>: выгружает все строки users в JSON-массив
+pub fn export_users(db_url: &str, path: &Path) -> Result<(), String> {
+    let mut client = Client::connect(db_url, NoTls).map_err(|e| format!("DB connection error: {}", e))?;
+
+    let rows = client
+        .query("SELECT id, name, email FROM users", &[])
+        .map_err(|e| format!("DB query error: {}", e))?;
+
+    let users: Vec<User> = rows
+        .into_iter()
+        .map(|row| User { id: row.get(0), name: row.get(1), email: row.get(2) })
+        .collect();
+
+    let data = serde_json::to_string_pretty(&users).map_err(|e| format!("unable to serialize users: {}", e))?;
+    fs::write(path, data).map_err(|e| format!("unable to write {}: {}", path.display(), e))?;
+
+    println!("Exported {} user(s) to {}", users.len(), path.display());
+    Ok(())
+}
@@ -1,55 +1,219 @@
-use postgres::{Client, NoTls};
-use postgres::Error as PostgresError;
+use r2d2::Pool;
+use r2d2_postgres::PostgresConnectionManager;
 use std::net::{TcpListener, TcpStream};
 use std::io::{Read, Write};
 use std::env;
 use std::str;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 // Внешние крейты
 #[macro_use]
 extern crate serde_derive;
 
-// Модель данных
-#[derive(Serialize, Deserialize, Debug)]
-struct User {
-    id: Option<i32>,
-    name: String,
-    email: String,
-}
+mod auth;
+mod cli;
+mod metrics;
+mod store;
+mod validation;
+
+use auth::PlainPassword;
+use store::{Backend, InMemoryStore, ListParams, PostgresStore, SortColumn, SortOrder, StoreError, User};
+use validation::Check;
+
+// Пул соединений с базой данных
+type DbPool = Pool<PostgresConnectionManager<postgres::NoTls>>;
 
 // Константы
 // ВАЖНО: env! работает только во время компиляции. Для корректной работы в Dockerfile
 // мы используем переменную DATABASE_URL, которую передает Cargo
 const DB_URL: &str = env!("DATABASE_URL");
+const DEFAULT_POOL_SIZE: u32 = 10;
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(200);
 const OK_RESPONSE: &str = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n";
 const NOT_FOUND: &str = "HTTP/1.1 404 NOT FOUND\r\n\r\n";
 const INTERNAL_ERROR: &str = "HTTP/1.1 500 INTERNAL ERROR\r\n\r\n";
 const BAD_REQUEST: &str = "HTTP/1.1 400 BAD REQUEST\r\nContent-Type: text/plain\r\n\r\n";
+// Для ответов ValidationError::to_json(), которые иначе уходили бы под text/plain
+const BAD_REQUEST_JSON: &str = "HTTP/1.1 400 BAD REQUEST\r\nContent-Type: application/json\r\n\r\n";
+const CONFLICT: &str = "HTTP/1.1 409 CONFLICT\r\nContent-Type: text/plain\r\n\r\n";
+const METRICS_RESPONSE: &str = "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\n\r\n";
+const MAX_LIST_LIMIT: i64 = 200;
 
 fn main() {
-    // Установка базы данных
-    if let Err(e) = set_database() {
-        eprintln!("Error setting database: {}", e);
+    let args: Vec<String> = env::args().collect();
+    if args.len() > 1 {
+        if let Err(e) = run_cli(&args[1..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
         return;
     }
 
+    let store = match build_store() {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Error setting up storage backend: {}", e);
+            return;
+        }
+    };
+
     let listener = TcpListener::bind("0.0.0.0:8080").unwrap();
+    listener.set_nonblocking(true).unwrap();
     println!("Server listening on port 8080");
 
-    for stream in listener.incoming() {
-        match stream {
-            Ok(stream) => {
-                handle_client(stream);
+    let shutdown = Arc::new(AtomicBool::new(false));
+    if let Err(e) = signal_hook::flag::register(signal_hook::consts::SIGTERM, Arc::clone(&shutdown)) {
+        eprintln!("Unable to register SIGTERM handler: {}", e);
+    }
+
+    let worker_count = env::var("WORKER_THREADS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or_else(|| thread::available_parallelism().map(|n| n.get()).unwrap_or(4));
+
+    let workers = ThreadPool::new(worker_count);
+
+    while !shutdown.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((stream, _addr)) => {
+                let store = Arc::clone(&store);
+                workers.execute(move || handle_client(stream, store.as_ref()));
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                thread::sleep(ACCEPT_POLL_INTERVAL);
             }
             Err(e) => {
                 eprintln!("Unable to connect: {}", e);
             }
         }
     }
+
+    println!("SIGTERM received, draining queued requests before exit");
+    drop(workers);
+}
+
+// Разбирает аргументы командной строки и запускает maintenance-подкоманды
+// import/export вместо HTTP-сервера.
+fn run_cli(args: &[String]) -> Result<(), String> {
+    match args.first().map(|s| s.as_str()) {
+        Some("import") => {
+            let path = args.get(1).ok_or("usage: import <file.json>")?;
+            cli::import_users(DB_URL, std::path::Path::new(path))
+        }
+        Some("export") => {
+            let path = args.get(1).ok_or("usage: export <file.json>")?;
+            cli::export_users(DB_URL, std::path::Path::new(path))
+        }
+        Some(other) => Err(format!("unknown subcommand: {}", other)),
+        None => Err("usage: <import|export> <file.json>".to_string()),
+    }
+}
+
+// Выбирает реализацию хранилища по переменной STORAGE_BACKEND ("postgres" по
+// умолчанию, либо "memory" для тестов/локальной разработки без живого Postgres).
+fn build_store() -> Result<Arc<dyn Backend>, String> {
+    match env::var("STORAGE_BACKEND").unwrap_or_else(|_| "postgres".to_string()).as_str() {
+        "memory" => Ok(Arc::new(InMemoryStore::new())),
+        "postgres" => {
+            store::set_database(DB_URL).map_err(|e| format!("error setting database: {}", e))?;
+
+            let pool_size = env::var("DB_POOL_SIZE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(DEFAULT_POOL_SIZE);
+
+            let pool = store::build_pool(DB_URL, pool_size)
+                .map_err(|e| format!("error building connection pool: {}", e))?;
+
+            Ok(Arc::new(PostgresStore::new(pool)))
+        }
+        other => Err(format!("unknown STORAGE_BACKEND: {}", other)),
+    }
+}
+
+// *** ПУЛ РАБОЧИХ ПОТОКОВ ***
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+enum Message {
+    NewJob(Job),
+    Terminate,
+}
+
+// Пул фиксированного размера: заявки из accept-цикла складываются в очередь,
+// а воркеры разбирают их параллельно, чтобы один медленный запрос к БД
+// не блокировал остальных клиентов.
+struct ThreadPool {
+    workers: Vec<Worker>,
+    sender: mpsc::Sender<Message>,
+}
+
+impl ThreadPool {
+    fn new(size: usize) -> ThreadPool {
+        let size = size.max(1);
+        let (sender, receiver) = mpsc::channel();
+        let receiver = Arc::new(std::sync::Mutex::new(receiver));
+
+        let mut workers = Vec::with_capacity(size);
+        for _ in 0..size {
+            workers.push(Worker::new(Arc::clone(&receiver)));
+        }
+
+        ThreadPool { workers, sender }
+    }
+
+    fn execute<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        if self.sender.send(Message::NewJob(Box::new(f))).is_err() {
+            eprintln!("Unable to queue request: worker pool is shutting down");
+        }
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        // Сигналим каждому воркеру завершиться только после того, как очередь опустеет,
+        // поэтому уже принятые запросы успевают обработаться перед остановкой.
+        for _ in &self.workers {
+            let _ = self.sender.send(Message::Terminate);
+        }
+
+        for worker in &mut self.workers {
+            if let Some(thread) = worker.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+struct Worker {
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl Worker {
+    fn new(receiver: Arc<std::sync::Mutex<mpsc::Receiver<Message>>>) -> Worker {
+        let thread = thread::spawn(move || loop {
+            let message = receiver.lock().unwrap().recv();
+
+            match message {
+                Ok(Message::NewJob(job)) => job(),
+                Ok(Message::Terminate) => break,
+                Err(_) => break,
+            }
+        });
+
+        Worker { thread: Some(thread) }
+    }
 }
 
 // *** ОСНОВНАЯ ФУНКЦИЯ ЧТЕНИЯ/ОБРАБОТКИ HTTP ***
-fn handle_client(mut stream: TcpStream) {
+fn handle_client(mut stream: TcpStream, store: &dyn Backend) {
     // Используем большой буфер для чтения первой части запроса
     let mut initial_buffer = [0; 4096];
 
@@ -97,14 +261,54 @@ fn handle_client(mut stream: TcpStream) {
     // Определяем тип запроса по первой строке
     let first_line = full_request.lines().next().unwrap_or(NOT_FOUND);
 
-    // В обработчики передается ПОЛНЫЙ запрос
-    let (status_line, content) = match first_line {
-        r if r.starts_with("POST /users") => handle_post_request(&full_request),
-        r if r.starts_with("GET /users/") => handle_get_request(&full_request),
-        r if r.starts_with("GET /users") => handle_get_all_request(&full_request),
-        r if r.starts_with("PUT /users/") => handle_put_request(&full_request),
-        r if r.starts_with("DELETE /users/") => handle_delete_request(&full_request),
-        _ => (NOT_FOUND.to_string(), "404 not found".to_string()),
+    // В обработчики передается ПОЛНЫЙ запрос и хранилище. Каждый маршрут оборачивается
+    // таймером метрик, чтобы считать запросы/статусы/длительность по method+route.
+    let (method, route): (&'static str, &'static str) = match first_line {
+        r if r.starts_with("GET /metrics") => ("GET", "/metrics"),
+        r if r.starts_with("POST /register") => ("POST", "/register"),
+        r if r.starts_with("POST /login") => ("POST", "/login"),
+        r if r.starts_with("POST /users") => ("POST", "/users"),
+        r if r.starts_with("GET /users/") => ("GET", "/users/:id"),
+        r if r.starts_with("GET /users") => ("GET", "/users"),
+        r if r.starts_with("PUT /users/") => ("PUT", "/users/:id"),
+        r if r.starts_with("DELETE /users/") => ("DELETE", "/users/:id"),
+        _ => ("UNKNOWN", "/unknown"),
+    };
+
+    let timer = metrics::start(method, route);
+
+    let (status_line, content, set_cookie) = match first_line {
+        r if r.starts_with("GET /metrics") => (METRICS_RESPONSE.to_string(), metrics::render(), None),
+        r if r.starts_with("POST /register") => handle_register_request(&full_request, store),
+        r if r.starts_with("POST /login") => handle_login_request(&full_request, store),
+        r if r.starts_with("POST /users") => {
+            let (s, c) = handle_post_request(&full_request, store);
+            (s, c, None)
+        }
+        r if r.starts_with("GET /users/") => {
+            let (s, c) = handle_get_request(&full_request, store);
+            (s, c, None)
+        }
+        r if r.starts_with("GET /users") => {
+            let (s, c) = handle_get_all_request(&full_request, store);
+            (s, c, None)
+        }
+        r if r.starts_with("PUT /users/") => {
+            let (s, c) = handle_put_request(&full_request, store);
+            (s, c, None)
+        }
+        r if r.starts_with("DELETE /users/") => {
+            let (s, c) = handle_delete_request(&full_request, store);
+            (s, c, None)
+        }
+        _ => (NOT_FOUND.to_string(), "404 not found".to_string(), None),
+    };
+
+    timer.observe(status_code(&status_line));
+
+    let status_line = match set_cookie {
+        Some(cookie) => status_line.replacen("\r\n\r\n", &format!("Set-Cookie: {}\r\n\r\n", cookie), 1),
+        None => status_line,
     };
 
     stream.write_all(format!("{}{}", status_line, content).as_bytes()).unwrap_or_default();
@@ -121,28 +325,169 @@ fn get_user_request_body(request: &str) -> Result<User, serde_json::Error> {
     Ok(user)
 }
 
+// Конверт ответа GET /users: страница результатов плюс общее количество
+#[derive(Serialize)]
+struct UsersPage {
+    users: Vec<User>,
+    total: i64,
+}
+
+// Разбирает query-строку "GET /users?limit=50&offset=100&sort=name&order=desc&name_like=foo"
+// в типизированные ListParams, отклоняя неизвестные столбцы сортировки.
+fn parse_list_params(first_line: &str) -> Result<ListParams, String> {
+    let mut params = ListParams::default();
+
+    let query = match first_line.split_once('?') {
+        Some((_, rest)) => rest.split_whitespace().next().unwrap_or_default(),
+        None => return Ok(params),
+    };
+
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        match key {
+            "limit" => params.limit = value.parse().map_err(|_| "Invalid limit".to_string())?,
+            "offset" => params.offset = value.parse().map_err(|_| "Invalid offset".to_string())?,
+            "sort" => params.sort = SortColumn::parse(value).ok_or_else(|| format!("Invalid sort column: {}", value))?,
+            "order" => params.order = SortOrder::parse(value).ok_or_else(|| format!("Invalid order: {}", value))?,
+            "name_like" => params.name_like = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    if params.limit < 0 {
+        return Err("Invalid limit: must not be negative".to_string());
+    }
+    if params.limit > MAX_LIST_LIMIT {
+        return Err(format!("Invalid limit: must not exceed {}", MAX_LIST_LIMIT));
+    }
+    if params.offset < 0 {
+        return Err("Invalid offset: must not be negative".to_string());
+    }
+
+    Ok(params)
+}
+
+// Извлекает код статуса из строки вида "HTTP/1.1 200 OK\r\n..."
+fn status_code(status_line: &str) -> u16 {
+    status_line.split_whitespace().nth(1).and_then(|s| s.parse().ok()).unwrap_or(0)
+}
+
 // Извлекает ID из URL (например, /users/123)
 fn get_id(request: &str) -> &str {
     request.split('/').nth(2).unwrap_or_default().split_whitespace().next().unwrap_or_default()
 }
 
-// Создает таблицу в БД
-fn set_database() -> Result<(), PostgresError> {
-    let mut client = Client::connect(DB_URL, NoTls)?;
-    client.batch_execute(
-        "CREATE TABLE IF NOT EXISTS users (
-            id SERIAL PRIMARY KEY,
-            name VARCHAR NOT NULL,
-            email VARCHAR NOT NULL
-        )",
-    )?;
-    Ok(())
+// Данные для POST /register и POST /login
+#[derive(Serialize, Deserialize, Debug)]
+struct Credentials {
+    name: Option<String>,
+    email: String,
+    password: String,
+}
+
+fn current_unix_time() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() as i64
+}
+
+// Достает значение куки "session" из заголовка Cookie
+fn session_token(request: &str) -> Option<&str> {
+    request
+        .lines()
+        .find(|line| line.starts_with("Cookie:"))
+        .and_then(|line| line.split(':').nth(1))
+        .and_then(|cookies| cookies.split(';').find_map(|c| c.trim().strip_prefix("session=")))
+}
+
+// Проверяет, что запрос несет валидную, непросроченную сессию, и возвращает id пользователя
+fn authenticated_user(request: &str, store: &dyn Backend) -> Option<i32> {
+    let token = session_token(request)?;
+    store.session_user(token, current_unix_time()).ok()
+}
+
+// Переводит ошибку хранилища в HTTP-ответ
+fn store_error_response(e: StoreError) -> (String, String) {
+    match e {
+        StoreError::NotFound => (NOT_FOUND.to_string(), "User not found".to_string()),
+        StoreError::Conflict(msg) => (CONFLICT.to_string(), msg),
+        StoreError::Backend(msg) => {
+            eprintln!("Storage backend error: {}", msg);
+            (INTERNAL_ERROR.to_string(), "Internal error".to_string())
+        }
+    }
+}
+
+// *** АУТЕНТИФИКАЦИЯ ***
+
+// POST /register: валидация, хеширование пароля и создание пользователя
+fn handle_register_request(request: &str, store: &dyn Backend) -> (String, String, Option<String>) {
+    let body = request.split("\r\n\r\n").last().unwrap_or_default();
+    let creds: Credentials = match serde_json::from_str(body) {
+        Ok(c) => c,
+        Err(e) => return (BAD_REQUEST.to_string(), format!("Invalid registration data: {}", e), None),
+    };
+
+    if let Err(e) = creds.check() {
+        return (BAD_REQUEST_JSON.to_string(), e.to_json(), None);
+    }
+
+    // check() just confirmed creds.name is Some
+    let name = creds.name.clone().unwrap_or_default();
+    let password = PlainPassword::new(creds.password);
+    let password_hash = match auth::hash_password(&password) {
+        Ok(h) => h,
+        Err(e) => {
+            eprintln!("Password hashing error: {}", e);
+            return (INTERNAL_ERROR.to_string(), "Internal error".to_string(), None);
+        }
+    };
+
+    match store.register(&name, &creds.email, &password_hash) {
+        Ok(_) => (OK_RESPONSE.to_string(), "User registered".to_string(), None),
+        Err(e) => {
+            let (s, c) = store_error_response(e);
+            (s, c, None)
+        }
+    }
+}
+
+// POST /login: сверяет пароль и выдает токен сессии через Set-Cookie
+fn handle_login_request(request: &str, store: &dyn Backend) -> (String, String, Option<String>) {
+    let body = request.split("\r\n\r\n").last().unwrap_or_default();
+    let creds: Credentials = match serde_json::from_str(body) {
+        Ok(c) => c,
+        Err(e) => return (BAD_REQUEST.to_string(), format!("Invalid login data: {}", e), None),
+    };
+
+    let (user_id, password_hash) = match store.credentials_for_email(&creds.email) {
+        Ok(v) => v,
+        Err(_) => return (BAD_REQUEST.to_string(), "Invalid email or password".to_string(), None),
+    };
+
+    let password = PlainPassword::new(creds.password);
+    match auth::verify_password(&password, &password_hash) {
+        Ok(true) => {}
+        Ok(false) => return (BAD_REQUEST.to_string(), "Invalid email or password".to_string(), None),
+        Err(e) => {
+            eprintln!("Password verification error: {}", e);
+            return (INTERNAL_ERROR.to_string(), "Internal error".to_string(), None);
+        }
+    }
+
+    let token = auth::generate_session_token();
+    let expires_at = current_unix_time() + auth::SESSION_TTL_SECONDS;
+
+    if let Err(e) = store.create_session(&token, user_id, expires_at) {
+        let (s, c) = store_error_response(e);
+        return (s, c, None);
+    }
+
+    (OK_RESPONSE.to_string(), "Logged in".to_string(), Some(format!("session={}; HttpOnly; Path=/", token)))
 }
 
 // *** ОБРАБОТЧИКИ CRUD ***
 
 // POST: Создание пользователя
-fn handle_post_request(request: &str) -> (String, String) {
+fn handle_post_request(request: &str, store: &dyn Backend) -> (String, String) {
     let user: User = match get_user_request_body(request) {
         Ok(u) => u,
         Err(e) => {
@@ -151,119 +496,89 @@ fn handle_post_request(request: &str) -> (String, String) {
         }
     };
 
-    match Client::connect(DB_URL, NoTls) {
-        Ok(mut client) => {
-            if let Err(e) = client.execute(
-                "INSERT INTO users (name, email) VALUES ($1, $2)",
-                &[&user.name, &user.email],
-            ) {
-                eprintln!("DB execution error: {}", e);
-                return (INTERNAL_ERROR.to_string(), "DB error".to_string());
-            }
-            (OK_RESPONSE.to_string(), "User created".to_string())
-        }
-        Err(e) => {
-            eprintln!("DB connection error: {}", e);
-            (INTERNAL_ERROR.to_string(), "Internal error".to_string())
-        }
+    if let Err(e) = user.check() {
+        return (BAD_REQUEST_JSON.to_string(), e.to_json());
+    }
+
+    match store.create(&user) {
+        Ok(_) => (OK_RESPONSE.to_string(), "User created".to_string()),
+        Err(e) => store_error_response(e),
     }
 }
 
 // GET: Получение одного пользователя по ID
-fn handle_get_request(request: &str) -> (String, String) {
+fn handle_get_request(request: &str, store: &dyn Backend) -> (String, String) {
     let id = match get_id(request).parse::<i32>() {
         Ok(i) => i,
         Err(_) => return (NOT_FOUND.to_string(), "Invalid ID or ID missing".to_string()),
     };
 
-    match Client::connect(DB_URL, NoTls) {
-        Ok(mut client) => {
-            match client.query_one("SELECT id, name, email FROM users WHERE id = $1", &[&id]) {
-                Ok(row) => {
-                    let user = User {
-                        id: row.get(0),
-                        name: row.get(1),
-                        email: row.get(2),
-                    };
-                    (OK_RESPONSE.to_string(), serde_json::to_string(&user).unwrap_or_default())
-                }
-                Err(_) => (NOT_FOUND.to_string(), "User not found".to_string()),
-            }
-        }
-        Err(_) => (INTERNAL_ERROR.to_string(), "Internal error".to_string()),
+    match store.get(id) {
+        Ok(user) => (OK_RESPONSE.to_string(), serde_json::to_string(&user).unwrap_or_default()),
+        Err(e) => store_error_response(e),
     }
 }
 
 // GET: Получение всех пользователей
-fn handle_get_all_request(_request: &str) -> (String, String) {
-    match Client::connect(DB_URL, NoTls) {
-        Ok(mut client) => {
-            let mut users = Vec::new();
-
-            match client.query("SELECT id, name, email FROM users", &[]) {
-                Ok(rows) => {
-                    for row in rows {
-                        users.push(User {
-                            id: row.get(0),
-                            name: row.get(1),
-                            email: row.get(2),
-                        });
-                    }
-                    (OK_RESPONSE.to_string(), serde_json::to_string(&users).unwrap_or_default())
-                }
-                Err(_) => (INTERNAL_ERROR.to_string(), "Error querying users".to_string()),
-            }
+fn handle_get_all_request(request: &str, store: &dyn Backend) -> (String, String) {
+    let first_line = request.lines().next().unwrap_or_default();
+    let params = match parse_list_params(first_line) {
+        Ok(p) => p,
+        Err(msg) => return (BAD_REQUEST.to_string(), msg),
+    };
+
+    match store.list_page(&params) {
+        Ok((users, total)) => {
+            let page = UsersPage { users, total };
+            (OK_RESPONSE.to_string(), serde_json::to_string(&page).unwrap_or_default())
         }
-        Err(_) => (INTERNAL_ERROR.to_string(), "Internal error".to_string()),
+        Err(e) => store_error_response(e),
     }
 }
 
 // PUT: Обновление пользователя
-fn handle_put_request(request: &str) -> (String, String) {
+fn handle_put_request(request: &str, store: &dyn Backend) -> (String, String) {
     let id = match get_id(request).parse::<i32>() {
         Ok(i) => i,
         Err(_) => return (NOT_FOUND.to_string(), "Invalid ID or ID missing".to_string()),
     };
 
+    match authenticated_user(request, store) {
+        Some(session_user_id) if session_user_id == id => {}
+        Some(_) => return (BAD_REQUEST.to_string(), "Cannot modify another user's account".to_string()),
+        None => return (BAD_REQUEST.to_string(), "Authentication required".to_string()),
+    }
+
     let user = match get_user_request_body(request) {
         Ok(u) => u,
         Err(_) => return (BAD_REQUEST.to_string(), "Invalid user data".to_string()),
     };
 
-    match Client::connect(DB_URL, NoTls) {
-        Ok(mut client) => {
-            let rows_affected = client.execute(
-                "UPDATE users SET name = $1, email = $2 WHERE id = $3",
-                &[&user.name, &user.email, &id],
-            ).unwrap_or(0);
-
-            if rows_affected == 0 {
-                return (NOT_FOUND.to_string(), "User not found for update".to_string());
-            }
+    if let Err(e) = user.check() {
+        return (BAD_REQUEST_JSON.to_string(), e.to_json());
+    }
 
-            (OK_RESPONSE.to_string(), "User updated".to_string())
-        }
-        Err(_) => (INTERNAL_ERROR.to_string(), "Internal error".to_string()),
+    match store.update(id, &user) {
+        Ok(()) => (OK_RESPONSE.to_string(), "User updated".to_string()),
+        Err(e) => store_error_response(e),
     }
 }
 
 // DELETE: Удаление пользователя
-fn handle_delete_request(request: &str) -> (String, String) {
+fn handle_delete_request(request: &str, store: &dyn Backend) -> (String, String) {
     let id = match get_id(request).parse::<i32>() {
         Ok(i) => i,
         Err(_) => return (NOT_FOUND.to_string(), "Invalid ID or ID missing".to_string()),
     };
 
-    match Client::connect(DB_URL, NoTls) {
-        Ok(mut client) => {
-            let rows_affected = client.execute("DELETE FROM users WHERE id = $1", &[&id]).unwrap_or(0);
-
-            if rows_affected == 0 {
-                return (NOT_FOUND.to_string(), "User not found".to_string());
-            }
+    match authenticated_user(request, store) {
+        Some(session_user_id) if session_user_id == id => {}
+        Some(_) => return (BAD_REQUEST.to_string(), "Cannot delete another user's account".to_string()),
+        None => return (BAD_REQUEST.to_string(), "Authentication required".to_string()),
+    }
 
-            (OK_RESPONSE.to_string(), "User deleted".to_string())
-        }
-        Err(_) => (INTERNAL_ERROR.to_string(), "Internal error".to_string()),
+    match store.delete(id) {
+        Ok(()) => (OK_RESPONSE.to_string(), "User deleted".to_string()),
+        Err(e) => store_error_response(e),
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+// Границы гистограммы длительности запроса, в миллисекундах
+const DURATION_BUCKETS_MS: [f64; 7] = [5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0];
+
+#[derive(Default)]
+struct RouteStats {
+    requests_total: u64,
+    status_counts: HashMap<u16, u64>,
+    duration_bucket_counts: [u64; DURATION_BUCKETS_MS.len() + 1],
+    duration_sum_ms: f64,
+}
+
+// Процесс-глобальный реестр счетчиков и гистограмм, ключ - (метод, маршрут)
+struct Registry {
+    routes: Mutex<HashMap<(&'static str, &'static str), RouteStats>>,
+}
+
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+fn registry() -> &'static Registry {
+    REGISTRY.get_or_init(|| Registry { routes: Mutex::new(HashMap::new()) })
+}
+
+// Таймер одного запроса: создается перед вызовом обработчика, завершается вызовом observe()
+pub struct Timer {
+    method: &'static str,
+    route: &'static str,
+    start: Instant,
+}
+
+pub fn start(method: &'static str, route: &'static str) -> Timer {
+    Timer { method, route, start: Instant::now() }
+}
+
+impl Timer {
+    pub fn observe(self, status: u16) {
+        let elapsed_ms = self.start.elapsed().as_secs_f64() * 1000.0;
+        let mut routes = registry().routes.lock().unwrap();
+        let stats = routes.entry((self.method, self.route)).or_default();
+
+        stats.requests_total += 1;
+        *stats.status_counts.entry(status).or_insert(0) += 1;
+        stats.duration_sum_ms += elapsed_ms;
+
+        let bucket = DURATION_BUCKETS_MS.iter().position(|bound| elapsed_ms <= *bound).unwrap_or(DURATION_BUCKETS_MS.len());
+        stats.duration_bucket_counts[bucket] += 1;
+    }
+}
+
+// Рендерит накопленные метрики в текстовом формате Prometheus
+pub fn render() -> String {
+    let routes = registry().routes.lock().unwrap();
+    let mut out = String::new();
+
+    out.push_str("# HELP http_requests_total Total HTTP requests by method and route\n");
+    out.push_str("# TYPE http_requests_total counter\n");
+    for ((method, route), stats) in routes.iter() {
+        out.push_str(&format!("http_requests_total{{method=\"{}\",route=\"{}\"}} {}\n", method, route, stats.requests_total));
+    }
+
+    out.push_str("# HELP http_response_status_total Responses by route and status code\n");
+    out.push_str("# TYPE http_response_status_total counter\n");
+    for ((method, route), stats) in routes.iter() {
+        for (status, count) in &stats.status_counts {
+            out.push_str(&format!(
+                "http_response_status_total{{method=\"{}\",route=\"{}\",status=\"{}\"}} {}\n",
+                method, route, status, count
+            ));
+        }
+    }
+
+    out.push_str("# HELP http_request_duration_ms Request duration in milliseconds\n");
+    out.push_str("# TYPE http_request_duration_ms histogram\n");
+    for ((method, route), stats) in routes.iter() {
+        let mut cumulative = 0;
+        for (i, bound) in DURATION_BUCKETS_MS.iter().enumerate() {
+            cumulative += stats.duration_bucket_counts[i];
+            out.push_str(&format!(
+                "http_request_duration_ms_bucket{{method=\"{}\",route=\"{}\",le=\"{}\"}} {}\n",
+                method, route, bound, cumulative
+            ));
+        }
+        cumulative += stats.duration_bucket_counts[DURATION_BUCKETS_MS.len()];
+        out.push_str(&format!(
+            "http_request_duration_ms_bucket{{method=\"{}\",route=\"{}\",le=\"+Inf\"}} {}\n",
+            method, route, cumulative
+        ));
+        out.push_str(&format!("http_request_duration_ms_sum{{method=\"{}\",route=\"{}\"}} {}\n", method, route, stats.duration_sum_ms));
+        out.push_str(&format!("http_request_duration_ms_count{{method=\"{}\",route=\"{}\"}} {}\n", method, route, stats.requests_total));
+    }
+
+    out
+}
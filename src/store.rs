@@ -0,0 +1,585 @@
+use crate::DbPool;
+use postgres::NoTls;
+use r2d2_postgres::PostgresConnectionManager;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// Модель данных
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct User {
+    pub id: Option<i32>,
+    pub name: String,
+    pub email: String,
+}
+
+// Ошибки хранилища, единые для всех реализаций UserStore
+#[derive(Debug)]
+pub enum StoreError {
+    NotFound,
+    Conflict(String),
+    Backend(String),
+}
+
+impl std::fmt::Display for StoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StoreError::NotFound => write!(f, "record not found"),
+            StoreError::Conflict(msg) => write!(f, "conflict: {}", msg),
+            StoreError::Backend(msg) => write!(f, "storage error: {}", msg),
+        }
+    }
+}
+
+impl From<postgres::Error> for StoreError {
+    fn from(e: postgres::Error) -> Self {
+        if e.code() == Some(&postgres::error::SqlState::UNIQUE_VIOLATION) {
+            return StoreError::Conflict("email already registered".to_string());
+        }
+        StoreError::Backend(e.to_string())
+    }
+}
+
+impl From<r2d2::Error> for StoreError {
+    fn from(e: r2d2::Error) -> Self {
+        StoreError::Backend(e.to_string())
+    }
+}
+
+// Абстракция над хранилищем пользователей. Отделяет CRUD-логику обработчиков
+// от конкретной БД, чтобы их можно было тестировать без живого Postgres
+// и при необходимости подменить бэкенд через STORAGE_BACKEND.
+pub trait UserStore: Send + Sync {
+    fn create(&self, user: &User) -> Result<i32, StoreError>;
+    fn get(&self, id: i32) -> Result<User, StoreError>;
+    fn list_page(&self, params: &ListParams) -> Result<(Vec<User>, i64), StoreError>;
+    fn update(&self, id: i32, user: &User) -> Result<(), StoreError>;
+    fn delete(&self, id: i32) -> Result<(), StoreError>;
+}
+
+// Столбец, допустимый для сортировки GET /users. Белый список защищает от
+// SQL-инъекции через query-параметр sort, т.к. имя столбца нельзя параметризовать.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortColumn {
+    Id,
+    Name,
+    Email,
+}
+
+impl SortColumn {
+    pub fn parse(raw: &str) -> Option<SortColumn> {
+        match raw {
+            "id" => Some(SortColumn::Id),
+            "name" => Some(SortColumn::Name),
+            "email" => Some(SortColumn::Email),
+            _ => None,
+        }
+    }
+
+    fn as_sql(&self) -> &'static str {
+        match self {
+            SortColumn::Id => "id",
+            SortColumn::Name => "name",
+            SortColumn::Email => "email",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    pub fn parse(raw: &str) -> Option<SortOrder> {
+        match raw {
+            "asc" => Some(SortOrder::Asc),
+            "desc" => Some(SortOrder::Desc),
+            _ => None,
+        }
+    }
+
+    fn as_sql(&self) -> &'static str {
+        match self {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        }
+    }
+}
+
+// Параметры постраничного листинга GET /users
+#[derive(Debug, Clone)]
+pub struct ListParams {
+    pub limit: i64,
+    pub offset: i64,
+    pub sort: SortColumn,
+    pub order: SortOrder,
+    pub name_like: Option<String>,
+}
+
+impl Default for ListParams {
+    fn default() -> Self {
+        ListParams { limit: 50, offset: 0, sort: SortColumn::Id, order: SortOrder::Asc, name_like: None }
+    }
+}
+
+// Абстракция над аутентификацией, отдельная от UserStore: хранит пароли
+// и сессии, но не участвует в обычном CRUD над пользователями.
+pub trait SessionStore: Send + Sync {
+    fn register(&self, name: &str, email: &str, password_hash: &str) -> Result<i32, StoreError>;
+    fn credentials_for_email(&self, email: &str) -> Result<(i32, String), StoreError>;
+    fn create_session(&self, token: &str, user_id: i32, expires_at: i64) -> Result<(), StoreError>;
+    fn session_user(&self, token: &str, now: i64) -> Result<i32, StoreError>;
+}
+
+// Бэкенд хранилища реализует обе абстракции сразу: CRUD над пользователями
+// и аутентификацию поверх тех же данных.
+pub trait Backend: UserStore + SessionStore {}
+impl<T: UserStore + SessionStore> Backend for T {}
+
+// *** POSTGRES ***
+
+pub struct PostgresStore {
+    pool: DbPool,
+}
+
+impl PostgresStore {
+    pub fn new(pool: DbPool) -> PostgresStore {
+        PostgresStore { pool }
+    }
+}
+
+impl UserStore for PostgresStore {
+    fn create(&self, user: &User) -> Result<i32, StoreError> {
+        let mut client = self.pool.get()?;
+        let row = client.query_one(
+            "INSERT INTO users (name, email) VALUES ($1, $2) RETURNING id",
+            &[&user.name, &user.email],
+        )?;
+        Ok(row.get(0))
+    }
+
+    fn get(&self, id: i32) -> Result<User, StoreError> {
+        let mut client = self.pool.get()?;
+        let row = client
+            .query_opt("SELECT id, name, email FROM users WHERE id = $1", &[&id])?
+            .ok_or(StoreError::NotFound)?;
+
+        Ok(User {
+            id: row.get(0),
+            name: row.get(1),
+            email: row.get(2),
+        })
+    }
+
+    fn list_page(&self, params: &ListParams) -> Result<(Vec<User>, i64), StoreError> {
+        let mut client = self.pool.get()?;
+
+        let where_clause = if params.name_like.is_some() { "WHERE name ILIKE $3" } else { "" };
+        let query = format!(
+            "SELECT id, name, email FROM users {} ORDER BY {} {} LIMIT $1 OFFSET $2",
+            where_clause,
+            params.sort.as_sql(),
+            params.order.as_sql(),
+        );
+        let count_where_clause = if params.name_like.is_some() { "WHERE name ILIKE $1" } else { "" };
+        let count_query = format!("SELECT COUNT(*) FROM users {}", count_where_clause);
+
+        let (rows, total) = match &params.name_like {
+            Some(name_like) => {
+                let pattern = format!("%{}%", name_like);
+                let rows = client.query(&query, &[&params.limit, &params.offset, &pattern])?;
+                let total: i64 = client.query_one(&count_query, &[&pattern])?.get(0);
+                (rows, total)
+            }
+            None => {
+                let rows = client.query(&query, &[&params.limit, &params.offset])?;
+                let total: i64 = client.query_one(&count_query, &[])?.get(0);
+                (rows, total)
+            }
+        };
+
+        let users = rows
+            .into_iter()
+            .map(|row| User { id: row.get(0), name: row.get(1), email: row.get(2) })
+            .collect();
+
+        Ok((users, total))
+    }
+
+    fn update(&self, id: i32, user: &User) -> Result<(), StoreError> {
+        let mut client = self.pool.get()?;
+        let rows_affected = client.execute(
+            "UPDATE users SET name = $1, email = $2 WHERE id = $3",
+            &[&user.name, &user.email, &id],
+        )?;
+
+        if rows_affected == 0 {
+            return Err(StoreError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn delete(&self, id: i32) -> Result<(), StoreError> {
+        let mut client = self.pool.get()?;
+        let rows_affected = client.execute("DELETE FROM users WHERE id = $1", &[&id])?;
+
+        if rows_affected == 0 {
+            return Err(StoreError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+impl SessionStore for PostgresStore {
+    fn register(&self, name: &str, email: &str, password_hash: &str) -> Result<i32, StoreError> {
+        let mut client = self.pool.get()?;
+        let row = client.query_one(
+            "INSERT INTO users (name, email, password_hash) VALUES ($1, $2, $3) RETURNING id",
+            &[&name, &email, &password_hash],
+        )?;
+        Ok(row.get(0))
+    }
+
+    // users_email_key гарантирует не более одной строки на email, так что
+    // query_opt без ORDER BY не может вернуть чужой хеш пароля. password_hash <> ''
+    // исключает пользователей, заведенных через POST /users (без register()) —
+    // для них нет пароля для проверки, так что это тот же случай, что и
+    // "пользователь не найден", а не ошибка хранилища.
+    fn credentials_for_email(&self, email: &str) -> Result<(i32, String), StoreError> {
+        let mut client = self.pool.get()?;
+        let row = client
+            .query_opt(
+                "SELECT id, password_hash FROM users WHERE email = $1 AND password_hash <> ''",
+                &[&email],
+            )?
+            .ok_or(StoreError::NotFound)?;
+        Ok((row.get(0), row.get(1)))
+    }
+
+    fn create_session(&self, token: &str, user_id: i32, expires_at: i64) -> Result<(), StoreError> {
+        let mut client = self.pool.get()?;
+        client.execute(
+            "INSERT INTO sessions (token, user_id, expires_at) VALUES ($1, $2, $3)",
+            &[&token, &user_id, &expires_at],
+        )?;
+        Ok(())
+    }
+
+    fn session_user(&self, token: &str, now: i64) -> Result<i32, StoreError> {
+        let mut client = self.pool.get()?;
+        let row = client
+            .query_opt(
+                "SELECT user_id FROM sessions WHERE token = $1 AND expires_at > $2",
+                &[&token, &now],
+            )?
+            .ok_or(StoreError::NotFound)?;
+        Ok(row.get(0))
+    }
+}
+
+// Собирает пул соединений Postgres. Размер пула настраивается через DB_POOL_SIZE,
+// чтобы не открывать новое TCP+auth соединение на каждый запрос.
+pub fn build_pool(db_url: &str, pool_size: u32) -> Result<DbPool, r2d2::Error> {
+    let manager = PostgresConnectionManager::new(db_url.parse().unwrap(), NoTls);
+    r2d2::Pool::builder().max_size(pool_size).build(manager)
+}
+
+// Создает таблицы в БД. Выполняется один раз при старте, поэтому использует
+// прямое подключение в обход пула, который на этом этапе еще не создан.
+pub fn set_database(db_url: &str) -> Result<(), postgres::Error> {
+    let mut client = postgres::Client::connect(db_url, NoTls)?;
+    client.batch_execute(
+        "CREATE TABLE IF NOT EXISTS users (
+            id SERIAL PRIMARY KEY,
+            name VARCHAR NOT NULL,
+            email VARCHAR NOT NULL
+        );
+        ALTER TABLE users ADD COLUMN IF NOT EXISTS password_hash VARCHAR NOT NULL DEFAULT '';
+        DO $$
+        BEGIN
+            IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'users_email_key') THEN
+                ALTER TABLE users ADD CONSTRAINT users_email_key UNIQUE (email);
+            END IF;
+        END$$;
+        CREATE TABLE IF NOT EXISTS sessions (
+            token VARCHAR PRIMARY KEY,
+            user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            expires_at BIGINT NOT NULL
+        )",
+    )?;
+    Ok(())
+}
+
+// *** IN-MEMORY (для тестов и локальной разработки без Postgres) ***
+
+#[derive(Default)]
+struct InMemoryState {
+    users: HashMap<i32, User>,
+    next_id: i32,
+    password_hashes: HashMap<i32, String>,
+    sessions: HashMap<String, (i32, i64)>,
+}
+
+pub struct InMemoryStore {
+    state: Mutex<InMemoryState>,
+}
+
+impl InMemoryStore {
+    pub fn new() -> InMemoryStore {
+        InMemoryStore {
+            state: Mutex::new(InMemoryState {
+                users: HashMap::new(),
+                next_id: 1,
+                password_hashes: HashMap::new(),
+                sessions: HashMap::new(),
+            }),
+        }
+    }
+}
+
+impl Default for InMemoryStore {
+    fn default() -> Self {
+        InMemoryStore::new()
+    }
+}
+
+impl UserStore for InMemoryStore {
+    fn create(&self, user: &User) -> Result<i32, StoreError> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.users.values().any(|u| u.email == user.email) {
+            return Err(StoreError::Conflict("email already registered".to_string()));
+        }
+
+        let id = state.next_id;
+        state.next_id += 1;
+        state.users.insert(id, User { id: Some(id), name: user.name.clone(), email: user.email.clone() });
+        Ok(id)
+    }
+
+    fn get(&self, id: i32) -> Result<User, StoreError> {
+        let state = self.state.lock().unwrap();
+        state.users.get(&id).cloned().ok_or(StoreError::NotFound)
+    }
+
+    fn list_page(&self, params: &ListParams) -> Result<(Vec<User>, i64), StoreError> {
+        let state = self.state.lock().unwrap();
+
+        let mut users: Vec<User> = state
+            .users
+            .values()
+            .filter(|u| match &params.name_like {
+                Some(needle) => u.name.to_lowercase().contains(&needle.to_lowercase()),
+                None => true,
+            })
+            .cloned()
+            .collect();
+
+        users.sort_by(|a, b| match params.sort {
+            SortColumn::Id => a.id.cmp(&b.id),
+            SortColumn::Name => a.name.cmp(&b.name),
+            SortColumn::Email => a.email.cmp(&b.email),
+        });
+        if params.order == SortOrder::Desc {
+            users.reverse();
+        }
+
+        let total = users.len() as i64;
+        let page = users
+            .into_iter()
+            .skip(params.offset.max(0) as usize)
+            .take(params.limit.max(0) as usize)
+            .collect();
+
+        Ok((page, total))
+    }
+
+    fn update(&self, id: i32, user: &User) -> Result<(), StoreError> {
+        let mut state = self.state.lock().unwrap();
+
+        if !state.users.contains_key(&id) {
+            return Err(StoreError::NotFound);
+        }
+        if state.users.iter().any(|(&other_id, u)| other_id != id && u.email == user.email) {
+            return Err(StoreError::Conflict("email already registered".to_string()));
+        }
+
+        let existing = state.users.get_mut(&id).unwrap();
+        existing.name = user.name.clone();
+        existing.email = user.email.clone();
+        Ok(())
+    }
+
+    fn delete(&self, id: i32) -> Result<(), StoreError> {
+        let mut state = self.state.lock().unwrap();
+        state.users.remove(&id).ok_or(StoreError::NotFound)?;
+
+        // В Postgres это делает ON DELETE CASCADE на sessions.user_id; здесь повторяем
+        // вручную, иначе токен сессии удаленного пользователя продолжит аутентифицировать.
+        state.password_hashes.remove(&id);
+        state.sessions.retain(|_, (user_id, _)| *user_id != id);
+
+        Ok(())
+    }
+}
+
+impl SessionStore for InMemoryStore {
+    fn register(&self, name: &str, email: &str, password_hash: &str) -> Result<i32, StoreError> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.users.values().any(|u| u.email == email) {
+            return Err(StoreError::Conflict("email already registered".to_string()));
+        }
+
+        let id = state.next_id;
+        state.next_id += 1;
+        state.users.insert(id, User { id: Some(id), name: name.to_string(), email: email.to_string() });
+        state.password_hashes.insert(id, password_hash.to_string());
+        Ok(id)
+    }
+
+    // Ищет пользователя по email через единственную пару (id, User) в HashMap,
+    // которая уникальна благодаря проверкам в create(), update() и register() — без этого при двух
+    // совпадающих email выбор строки был бы произвольным.
+    fn credentials_for_email(&self, email: &str) -> Result<(i32, String), StoreError> {
+        let state = self.state.lock().unwrap();
+        let (id, _) = state.users.iter().find(|(_, u)| u.email == email).ok_or(StoreError::NotFound)?;
+        let hash = state.password_hashes.get(id).cloned().ok_or(StoreError::NotFound)?;
+        Ok((*id, hash))
+    }
+
+    fn create_session(&self, token: &str, user_id: i32, expires_at: i64) -> Result<(), StoreError> {
+        let mut state = self.state.lock().unwrap();
+        state.sessions.insert(token.to_string(), (user_id, expires_at));
+        Ok(())
+    }
+
+    fn session_user(&self, token: &str, now: i64) -> Result<i32, StoreError> {
+        let state = self.state.lock().unwrap();
+        let (user_id, expires_at) = state.sessions.get(token).ok_or(StoreError::NotFound)?;
+        if *expires_at <= now {
+            return Err(StoreError::NotFound);
+        }
+        Ok(*user_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(name: &str, email: &str) -> User {
+        User { id: None, name: name.to_string(), email: email.to_string() }
+    }
+
+    #[test]
+    fn register_then_credentials_for_email_round_trips() {
+        let store = InMemoryStore::new();
+        let id = store.register("Alice", "alice@example.com", "hash1").unwrap();
+
+        let (found_id, hash) = store.credentials_for_email("alice@example.com").unwrap();
+        assert_eq!(found_id, id);
+        assert_eq!(hash, "hash1");
+    }
+
+    #[test]
+    fn register_rejects_duplicate_email() {
+        let store = InMemoryStore::new();
+        store.register("Alice", "alice@example.com", "hash1").unwrap();
+
+        let err = store.register("Alice Two", "alice@example.com", "hash2").unwrap_err();
+        assert!(matches!(err, StoreError::Conflict(_)));
+    }
+
+    #[test]
+    fn create_rejects_duplicate_email() {
+        let store = InMemoryStore::new();
+        store.create(&user("Alice", "alice@example.com")).unwrap();
+
+        let err = store.create(&user("Alice Two", "alice@example.com")).unwrap_err();
+        assert!(matches!(err, StoreError::Conflict(_)));
+    }
+
+    #[test]
+    fn update_rejects_email_already_used_by_another_user() {
+        let store = InMemoryStore::new();
+        let alice_id = store.create(&user("Alice", "alice@example.com")).unwrap();
+        store.create(&user("Bob", "bob@example.com")).unwrap();
+
+        let err = store.update(alice_id, &user("Alice", "bob@example.com")).unwrap_err();
+        assert!(matches!(err, StoreError::Conflict(_)));
+    }
+
+    #[test]
+    fn update_allows_keeping_own_email() {
+        let store = InMemoryStore::new();
+        let alice_id = store.create(&user("Alice", "alice@example.com")).unwrap();
+
+        store.update(alice_id, &user("Alice Renamed", "alice@example.com")).unwrap();
+        assert_eq!(store.get(alice_id).unwrap().name, "Alice Renamed");
+    }
+
+    #[test]
+    fn session_user_accepts_unexpired_token_and_rejects_expired() {
+        let store = InMemoryStore::new();
+        let id = store.register("Alice", "alice@example.com", "hash1").unwrap();
+
+        store.create_session("tok-valid", id, 1_000).unwrap();
+        assert_eq!(store.session_user("tok-valid", 500).unwrap(), id);
+
+        store.create_session("tok-expired", id, 1_000).unwrap();
+        assert!(matches!(store.session_user("tok-expired", 1_000), Err(StoreError::NotFound)));
+    }
+
+    #[test]
+    fn session_user_rejects_unknown_token() {
+        let store = InMemoryStore::new();
+        assert!(matches!(store.session_user("no-such-token", 0), Err(StoreError::NotFound)));
+    }
+
+    #[test]
+    fn delete_removes_password_hash_and_sessions() {
+        let store = InMemoryStore::new();
+        let id = store.register("Alice", "alice@example.com", "hash1").unwrap();
+        store.create_session("tok-valid", id, 1_000).unwrap();
+
+        store.delete(id).unwrap();
+
+        assert!(matches!(store.credentials_for_email("alice@example.com"), Err(StoreError::NotFound)));
+        assert!(matches!(store.session_user("tok-valid", 0), Err(StoreError::NotFound)));
+    }
+
+    #[test]
+    fn list_page_sorts_filters_and_paginates() {
+        let store = InMemoryStore::new();
+        store.create(&user("Charlie", "charlie@example.com")).unwrap();
+        store.create(&user("Alice", "alice@example.com")).unwrap();
+        store.create(&user("Bob", "bob@example.com")).unwrap();
+
+        let params = ListParams { limit: 2, offset: 0, sort: SortColumn::Name, order: SortOrder::Asc, name_like: None };
+        let (page, total) = store.list_page(&params).unwrap();
+        assert_eq!(total, 3);
+        assert_eq!(page.iter().map(|u| u.name.as_str()).collect::<Vec<_>>(), vec!["Alice", "Bob"]);
+
+        let params = ListParams { limit: 2, offset: 2, sort: SortColumn::Name, order: SortOrder::Asc, name_like: None };
+        let (page, total) = store.list_page(&params).unwrap();
+        assert_eq!(total, 3);
+        assert_eq!(page.iter().map(|u| u.name.as_str()).collect::<Vec<_>>(), vec!["Charlie"]);
+
+        let params = ListParams { limit: 50, offset: 0, sort: SortColumn::Name, order: SortOrder::Desc, name_like: None };
+        let (page, _) = store.list_page(&params).unwrap();
+        assert_eq!(page.iter().map(|u| u.name.as_str()).collect::<Vec<_>>(), vec!["Charlie", "Bob", "Alice"]);
+
+        let params = ListParams {
+            limit: 50,
+            offset: 0,
+            sort: SortColumn::Name,
+            order: SortOrder::Asc,
+            name_like: Some("ali".to_string()),
+        };
+        let (page, total) = store.list_page(&params).unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(page[0].name, "Alice");
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{Credentials, User};
+
+// Нарушение одного поля при валидации
+#[derive(Serialize, Debug)]
+pub struct FieldViolation {
+    pub field: String,
+    pub message: String,
+}
+
+// Список нарушений, накопленный за один проход проверки
+#[derive(Serialize, Debug)]
+pub struct ValidationError {
+    pub errors: Vec<FieldViolation>,
+}
+
+impl ValidationError {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+// Реализуется моделями, которые нужно проверить перед записью в хранилище
+pub trait Check {
+    fn check(&self) -> Result<(), ValidationError>;
+}
+
+// Проверяет, что длина строки без начальных и конечных пробелов укладывается в [min, max],
+// иначе возвращает нарушение
+fn assert_length(field: &str, value: &str, min: usize, max: usize, msg: &str) -> Result<(), FieldViolation> {
+    let len = value.trim().chars().count();
+    if len < min || len > max {
+        return Err(FieldViolation { field: field.to_string(), message: msg.to_string() });
+    }
+    Ok(())
+}
+
+// Грубая проверка формы email: один '@', что-то до и после, точка в домене
+fn assert_email(field: &str, value: &str) -> Result<(), FieldViolation> {
+    let invalid = FieldViolation { field: field.to_string(), message: "must be a valid email address".to_string() };
+
+    let mut parts = value.split('@');
+    let (local, domain) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(local), Some(domain), None) => (local, domain),
+        _ => return Err(invalid),
+    };
+
+    if local.is_empty() || domain.is_empty() || !domain.contains('.') || value.contains(' ') {
+        return Err(invalid);
+    }
+
+    Ok(())
+}
+
+impl Check for User {
+    fn check(&self) -> Result<(), ValidationError> {
+        let mut errors = Vec::new();
+
+        if let Err(v) = assert_length("name", &self.name, 1, 100, "must be between 1 and 100 characters") {
+            errors.push(v);
+        }
+
+        if let Err(v) = assert_length("email", &self.email, 3, 254, "must be between 3 and 254 characters") {
+            errors.push(v);
+        } else if let Err(v) = assert_email("email", &self.email) {
+            errors.push(v);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError { errors })
+        }
+    }
+}
+
+impl Check for Credentials {
+    fn check(&self) -> Result<(), ValidationError> {
+        let mut errors = Vec::new();
+
+        match &self.name {
+            Some(name) => {
+                if let Err(v) = assert_length("name", name, 1, 100, "must be between 1 and 100 characters") {
+                    errors.push(v);
+                }
+            }
+            None => errors.push(FieldViolation { field: "name".to_string(), message: "is required".to_string() }),
+        }
+
+        if let Err(v) = assert_length("email", &self.email, 3, 254, "must be between 3 and 254 characters") {
+            errors.push(v);
+        } else if let Err(v) = assert_email("email", &self.email) {
+            errors.push(v);
+        }
+
+        if let Err(v) = assert_length("password", &self.password, 8, 256, "must be between 8 and 256 characters") {
+            errors.push(v);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError { errors })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_accepts_valid_fields() {
+        let user = User { id: None, name: "Alice".to_string(), email: "alice@example.com".to_string() };
+        assert!(user.check().is_ok());
+    }
+
+    #[test]
+    fn user_rejects_empty_name() {
+        let user = User { id: None, name: "".to_string(), email: "alice@example.com".to_string() };
+        let err = user.check().unwrap_err();
+        assert!(err.errors.iter().any(|v| v.field == "name"));
+    }
+
+    #[test]
+    fn user_rejects_malformed_email() {
+        let user = User { id: None, name: "Alice".to_string(), email: "not-an-email".to_string() };
+        let err = user.check().unwrap_err();
+        assert!(err.errors.iter().any(|v| v.field == "email"));
+    }
+
+    fn credentials(name: Option<&str>, email: &str, password: &str) -> Credentials {
+        Credentials { name: name.map(|n| n.to_string()), email: email.to_string(), password: password.to_string() }
+    }
+
+    #[test]
+    fn credentials_accepts_valid_fields() {
+        let creds = credentials(Some("Alice"), "alice@example.com", "hunter22");
+        assert!(creds.check().is_ok());
+    }
+
+    #[test]
+    fn credentials_rejects_missing_name() {
+        let creds = credentials(None, "alice@example.com", "hunter22");
+        let err = creds.check().unwrap_err();
+        assert!(err.errors.iter().any(|v| v.field == "name"));
+    }
+
+    #[test]
+    fn credentials_rejects_short_password() {
+        let creds = credentials(Some("Alice"), "alice@example.com", "short");
+        let err = creds.check().unwrap_err();
+        assert!(err.errors.iter().any(|v| v.field == "password"));
+    }
+
+    #[test]
+    fn credentials_rejects_malformed_email() {
+        let creds = credentials(Some("Alice"), "not-an-email", "hunter22");
+        let err = creds.check().unwrap_err();
+        assert!(err.errors.iter().any(|v| v.field == "email"));
+    }
+}